@@ -0,0 +1,159 @@
+// Copyright 2018 Phillip Lord, Newcastle University
+//
+// Licensed under either the Apache License, Version 2.0 or the MIT
+// licence at your option. This file may not be copied, modified or
+// distributed except according to those terms.
+
+/*!
+Companion derive macro for `enum_meta`, re-exported from the main
+crate as `enum_meta::Meta`.
+
+This lets metadata be declared inline on each variant instead of in a
+separate `meta!`/`lazy_meta!` block:
+
+```ignore
+#[derive(Meta)]
+#[meta(ty = "&'static str")]
+enum Colour {
+    #[meta("Red")]
+    Red,
+    #[meta("Orange")]
+    Orange,
+    #[meta("Green")]
+    Green,
+}
+```
+
+which expands to the same `impl Meta<&'static str> for Colour` that
+`meta!{ Colour, &'static str; Red, "Red"; ... }` produces. Add
+`#[meta(lazy)]` on the enum to get the `OnceLock`-backed table
+`lazy_meta!` produces instead, for metadata expressions that should
+only be computed once, on first access.
+
+A variant missing its `#[meta(...)]` attribute is a compile error,
+replacing the `meta_check` trick the declarative macros use for the
+same purpose.
+*/
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Lit, Meta as AttrMeta, NestedMeta, Variant};
+
+#[proc_macro_derive(Meta, attributes(meta))]
+pub fn derive_meta(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let enum_name = &input.ident;
+
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return syn::Error::new(Span::call_site(), "Meta can only be derived for enums")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let return_type = match enum_type_attr(&input.attrs) {
+        Ok(ty) => ty,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let lazy = has_lazy_attr(&input.attrs);
+
+    let mut variant_idents = Vec::new();
+    let mut meta_exprs = Vec::new();
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return syn::Error::new_spanned(
+                &variant.fields,
+                "Meta can only be derived for enums with unit variants",
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        let expr = match variant_meta_expr(variant) {
+            Ok(expr) => expr,
+            Err(e) => return e.to_compile_error().into(),
+        };
+
+        variant_idents.push(&variant.ident);
+        meta_exprs.push(expr);
+    }
+
+    let expanded = if lazy {
+        let storage = syn::Ident::new(
+            &format!("__{}_META", enum_name.to_string().to_uppercase()),
+            Span::call_site(),
+        );
+        quote! {
+            ::enum_meta::lazy_meta! {
+                #enum_name, #return_type, #storage;
+                #( #variant_idents, #meta_exprs );*
+            }
+        }
+    } else {
+        quote! {
+            ::enum_meta::meta! {
+                #enum_name, #return_type;
+                #( #variant_idents, #meta_exprs );*
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Reads the enum's metadata type from `#[meta(ty = "...")]`.
+fn enum_type_attr(attrs: &[syn::Attribute]) -> syn::Result<syn::Type> {
+    for attr in attrs {
+        if !attr.path.is_ident("meta") {
+            continue;
+        }
+        if let AttrMeta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(AttrMeta::NameValue(nv)) = nested {
+                    if nv.path.is_ident("ty") {
+                        if let Lit::Str(s) = nv.lit {
+                            return s.parse();
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Err(syn::Error::new(
+        Span::call_site(),
+        "derive(Meta) requires #[meta(ty = \"...\")] on the enum",
+    ))
+}
+
+/// Whether the enum carries `#[meta(lazy)]`.
+fn has_lazy_attr(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path.is_ident("meta")
+            && matches!(attr.parse_meta(), Ok(AttrMeta::List(list))
+                if list.nested.iter().any(|nested| matches!(nested,
+                    NestedMeta::Meta(AttrMeta::Path(path)) if path.is_ident("lazy"))))
+    })
+}
+
+/// Reads a variant's metadata expression from its `#[meta(...)]`
+/// attribute, failing at compile time if it is missing.
+fn variant_meta_expr(variant: &Variant) -> syn::Result<syn::Expr> {
+    for attr in &variant.attrs {
+        if !attr.path.is_ident("meta") {
+            continue;
+        }
+        return attr.parse_args::<syn::Expr>();
+    }
+    Err(syn::Error::new_spanned(
+        &variant.ident,
+        format!(
+            "variant `{}` is missing its #[meta(...)] attribute",
+            variant.ident
+        ),
+    ))
+}
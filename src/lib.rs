@@ -80,12 +80,32 @@ In this case, values are stored in a global variable whose name is
 provided (`META_Colour2` in this instance). Values returned are
 references to the given return type.
 
-Reverse lookup is not supported in-directly, by providing an `all`
-method which returns all the enum variants as a vector; this allows
-construction of a reverse lookup function; this is hard to achieve in
-general, requires putting a lot of constraints on the type of the
-metadata and can only sensibly support lookup by direct equality with
-the metadata.
+A companion derive macro is also available behind the `derive`
+feature, for declaring metadata inline on the variant rather than in a
+separate `meta!`/`lazy_meta!` block:
+
+```ignore
+#[derive(Meta)]
+#[meta(ty = "&'static str")]
+enum Colour {
+    #[meta("Red")]
+    Red,
+    #[meta("Orange")]
+    Orange,
+    #[meta("Green")]
+    Green,
+}
+```
+
+See `enum_meta_derive` for the full set of supported attributes.
+
+Reverse lookup is supported through the `from_meta` method, which
+takes a metadata value and returns the first variant declared with
+that value, or `None` if no variant matches. This is only meaningful
+when the metadata type supports equality; if two variants share the
+same metadata value, the first one declared wins. The `from_meta`
+generated by `lazy_meta!` looks values up in a hash table, so
+`lazy_meta!` only compiles for metadata types that are `Eq + Hash`.
 
 ```
 #[macro_use] extern crate enum_meta;
@@ -114,31 +134,106 @@ fn main() {
 
 
 */
+#![cfg_attr(not(feature = "std"), no_std)]
 #![macro_use]
 
-#[allow(unused_imports)]
-#[macro_use] extern crate lazy_static;
+// Lets the derive macro's `::enum_meta::...` paths resolve when it is
+// exercised from this crate's own tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as enum_meta;
 
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+extern crate alloc;
 
-pub use lazy_static::*;
+// `no_std` has no prelude equivalent for these, unlike `std`.
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::string::String;
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::vec::Vec;
 
+#[cfg(feature = "std")]
 pub use std::collections::HashMap;
-pub use std::mem::discriminant;
-pub use std::mem::Discriminant;
+// `lazy_meta!` keys its forward table on `Discriminant<T>`, which is `Eq +
+// Hash` but not `Ord`, so a `BTreeMap` will not do here; `hashbrown`
+// gives us a real hash map on `alloc`-only targets.
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+pub use hashbrown::HashMap;
+
+pub use core::mem::discriminant;
+pub use core::mem::Discriminant;
+
+#[cfg(feature = "derive")]
+pub use enum_meta_derive::Meta;
+
+/// A single-initialization cell used by `lazy_meta!` to build its
+/// lookup tables once, on first access. Backed by
+/// `std::sync::OnceLock` when the `std` feature is enabled (the
+/// default), and by `once_cell::race::OnceBox` on `alloc`-only
+/// targets, so `lazy_meta!` itself does not need to know which is in
+/// play.
+#[cfg(feature = "std")]
+pub type Lazy<T> = std::sync::OnceLock<T>;
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+pub struct Lazy<T>(once_cell::race::OnceBox<T>);
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+impl<T> Lazy<T> {
+    // Only a `const fn new` is provided, for use in `static`
+    // initialisers; this wrapper has no `Default` impl.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Lazy(once_cell::race::OnceBox::new())
+    }
+
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        self.0.get_or_init(|| alloc::boxed::Box::new(f()))
+    }
+}
 
 /// Trait for accessing metadata
 pub trait Meta<R>
     where Self:Sized {
     fn meta(&self) -> R;
     fn all() -> Vec<Self>;
+
+    /// Find the variant, if any, whose metadata is equal to `value`.
+    ///
+    /// This is the inverse of `meta`: where `meta` maps a variant to
+    /// its metadata, `from_meta` maps a metadata value back to a
+    /// variant. If several variants share the same metadata value,
+    /// the first one declared is returned. Only available when `R`
+    /// supports equality.
+    fn from_meta(value: &R) -> Option<Self>
+        where R: PartialEq
+    {
+        Self::all().into_iter().find(|variant| variant.meta() == *value)
+    }
+}
+
+/// Error returned by the `FromStr` implementation generated by
+/// `str_meta!` when no variant's metadata matches the input string.
+#[derive(Debug, Eq, PartialEq)]
+pub struct MetaParseError {
+    pub input: String,
+}
+
+impl core::fmt::Display for MetaParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "no variant with metadata {:?}", self.input)
+    }
 }
 
+// `core` has no `Error` trait; only implement it when `std` is around.
+#[cfg(feature = "std")]
+impl std::error::Error for MetaParseError {}
+
 #[macro_export]
 macro_rules! meta {
     ($enum_type:ident, $return_type:ty;
      $($enum_variant:ident, $return_value:expr);*
     ) => {
-        impl Meta<$return_type> for $enum_type {
+        impl $crate::Meta<$return_type> for $enum_type {
 
             fn meta(&self) -> $return_type {
                 match self {
@@ -158,12 +253,77 @@ macro_rules! meta {
                 ]
             }
         }
+
+        #[allow(dead_code)]
+        impl $enum_type {
+            /// The number of variants declared for this enum.
+            pub const VARIANT_COUNT: usize = [ $( stringify!($enum_variant) ),* ].len();
+
+            /// The zero-based position of this variant in the order
+            /// it was declared.
+            pub fn ordinal(&self) -> usize {
+                <$enum_type as $crate::Meta<$return_type>>::all().iter()
+                    .position(|v| $crate::discriminant(v) == $crate::discriminant(self))
+                    .unwrap()
+            }
+
+            /// The variant at zero-based position `n` in declaration
+            /// order, or `None` if `n` is out of range.
+            pub fn from_ordinal(n: usize) -> Option<Self> {
+                <$enum_type as $crate::Meta<$return_type>>::all().into_iter().nth(n)
+            }
+        }
     };
     // Trailing semi
     ($enum_type:ident, $return_type:ty;
      $($enum_variant:ident, $return_value:expr);+ ;
     ) => {
-        meta!{
+        $crate::meta!{
+            $enum_type, $return_type;
+            $( $enum_variant, $return_value );*
+        }
+    };
+}
+
+/// As `meta!`, but for enums whose metadata is textual (`&'static
+/// str` or `String`). In addition to `Meta`, this also generates
+/// `Display` (writing the variant's metadata) and `FromStr` (parsing
+/// it back via a linear scan of `all()`), mirroring strum's
+/// `Display`/`EnumString`. Parsing is case-sensitive and fails with
+/// `MetaParseError` when no variant's metadata matches the input.
+#[macro_export]
+macro_rules! str_meta {
+    ($enum_type:ident, $return_type:ty;
+     $($enum_variant:ident, $return_value:expr);*
+    ) => {
+        $crate::meta!{
+            $enum_type, $return_type;
+            $( $enum_variant, $return_value );*
+        }
+
+        impl ::std::fmt::Display for $enum_type {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(f, "{}", <$enum_type as $crate::Meta<$return_type>>::meta(self))
+            }
+        }
+
+        impl ::std::str::FromStr for $enum_type {
+            type Err = $crate::MetaParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <$enum_type as $crate::Meta<$return_type>>::all().into_iter()
+                    .find(|variant| {
+                        AsRef::<str>::as_ref(&<$enum_type as $crate::Meta<$return_type>>::meta(variant)) == s
+                    })
+                    .ok_or_else(|| $crate::MetaParseError { input: s.to_string() })
+            }
+        }
+    };
+    // Trailing semi
+    ($enum_type:ident, $return_type:ty;
+     $($enum_variant:ident, $return_value:expr);+ ;
+    ) => {
+        $crate::str_meta!{
             $enum_type, $return_type;
             $( $enum_variant, $return_value );*
         }
@@ -175,22 +335,19 @@ macro_rules! lazy_meta {
     ($enum_type:ident, $return_type:ty, $storage:ident;
      $($enum_variant:ident, $return_expr:expr);*
     ) => {
-        lazy_static! {
-            static ref $storage: HashMap<Discriminant<$enum_type>,$return_type>
-                = {
-                    let mut m = HashMap::new();
+        #[allow(non_upper_case_globals)]
+        static $storage: $crate::Lazy<$crate::HashMap<$crate::Discriminant<$enum_type>, $return_type>> = $crate::Lazy::new();
 
+        impl <'a> $crate::Meta<&'a $return_type> for $enum_type {
+            fn meta(&self) -> &'a $return_type {
+                $storage.get_or_init(|| {
+                    let mut m = $crate::HashMap::new();
                     $(
-                        m.insert(discriminant(&$enum_type::$enum_variant),
+                        m.insert($crate::discriminant(&$enum_type::$enum_variant),
                                  $return_expr);
                     )*
-                        m
-                };
-        }
-
-        impl <'a> Meta<&'a $return_type> for $enum_type {
-            fn meta(&self) -> &'a $return_type {
-                $storage.get(&discriminant(&self)).unwrap()
+                    m
+                }).get(&$crate::discriminant(self)).unwrap()
             }
 
             fn all() -> Vec<$enum_type>{
@@ -200,8 +357,37 @@ macro_rules! lazy_meta {
                     ),*
                 ]
             }
+
+            fn from_meta(value: &&'a $return_type) -> Option<Self>
+                where $return_type: Eq + ::core::hash::Hash
+            {
+                static REV: $crate::Lazy<$crate::HashMap<&'static $return_type, $crate::Discriminant<$enum_type>>> = $crate::Lazy::new();
+
+                let rev = REV.get_or_init(|| {
+                    let forward = $storage.get_or_init(|| {
+                        let mut m = $crate::HashMap::new();
+                        $(
+                            m.insert($crate::discriminant(&$enum_type::$enum_variant), $return_expr);
+                        )*
+                        m
+                    });
+
+                    let mut m = $crate::HashMap::new();
+                    $(
+                        m.entry(
+                            forward.get(&$crate::discriminant(&$enum_type::$enum_variant)).unwrap()
+                        ).or_insert($crate::discriminant(&$enum_type::$enum_variant));
+                    )*
+                    m
+                });
+
+                let target = rev.get(*value)?;
+                <$enum_type as $crate::Meta<&'a $return_type>>::all().into_iter()
+                    .find(|v| $crate::discriminant(v) == *target)
+            }
         }
 
+        #[allow(dead_code)]
         impl $enum_type {
             // This does nothing at all, but will fail if we do not pass all of
             // the entities that we need.
@@ -213,20 +399,96 @@ macro_rules! lazy_meta {
                     ),*
                 }
             }
+
+            /// The number of variants declared for this enum.
+            pub const VARIANT_COUNT: usize = [ $( stringify!($enum_variant) ),* ].len();
+
+            /// The zero-based position of this variant in the order
+            /// it was declared.
+            pub fn ordinal(&self) -> usize {
+                <$enum_type as $crate::Meta<&'static $return_type>>::all().iter()
+                    .position(|v| $crate::discriminant(v) == $crate::discriminant(self))
+                    .unwrap()
+            }
+
+            /// The variant at zero-based position `n` in declaration
+            /// order, or `None` if `n` is out of range.
+            pub fn from_ordinal(n: usize) -> Option<Self> {
+                <$enum_type as $crate::Meta<&'static $return_type>>::all().into_iter().nth(n)
+            }
         }
     };
     // Trailing semi
     ($enum_type:ident, $return_type:ty, $storage:ident;
      $($enum_variant:ident, $return_expr:expr);+ ;
     ) => {
-        lazy_meta!{
+        $crate::lazy_meta!{
             $enum_type, $return_type, $storage;
             $( $enum_variant, $return_expr );*
         }
     };
 }
 
-#[cfg(test)]
+/// Attaches a set of named, heterogeneous-keyed properties to each
+/// variant, for the common case where a single variant needs several
+/// independent attributes (for example an HTTP status needing both a
+/// numeric code and a category string) rather than one metadata
+/// value shared by every variant.
+///
+/// ```ignore
+/// meta_props!{
+///   EnumType, ValueType;
+///   VariantOne, { "key_one" => value, "key_two" => value };
+///   VariantTwo, { "key_one" => value, "key_two" => value };
+/// }
+/// ```
+///
+/// This generates two inherent methods on `EnumType`: `get_prop`,
+/// which looks a property up by key, and `prop_keys`, which lists the
+/// keys declared for that variant.
+#[macro_export]
+macro_rules! meta_props {
+    ($enum_type:ident, $value_type:ty;
+     $($enum_variant:ident, { $($key:expr => $value:expr),* $(,)? });*
+     $(;)?
+    ) => {
+        impl $enum_type {
+            /// Look up a named property of this variant. Returns
+            /// `None` if `key` was not declared for this variant.
+            pub fn get_prop(&self, key: &str) -> Option<&'static $value_type> {
+                match self {
+                    $(
+                        $enum_type::$enum_variant => {
+                            const PROPS: &[(&str, $value_type)] = &[
+                                $( ($key, $value) ),*
+                            ];
+                            PROPS.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+                        }
+                    ),*
+                }
+            }
+
+            /// The property keys declared for this variant, in
+            /// declaration order.
+            pub fn prop_keys(&self) -> &'static [&'static str] {
+                match self {
+                    $(
+                        $enum_type::$enum_variant => {
+                            const KEYS: &[&str] = &[ $( $key ),* ];
+                            KEYS
+                        }
+                    ),*
+                }
+            }
+        }
+    };
+}
+
+// These tests lean on std-only conveniences (`format!`, `to_string`,
+// the `vec!` macro resolving without an explicit `alloc`
+// import, ...), so they only run with `std` enabled; `alloc`-only
+// targets are exercised via `cargo build`/`cargo clippy` instead.
+#[cfg(all(test, feature = "std"))]
 mod test {
     use super::*;
 
@@ -315,6 +577,101 @@ mod test {
         assert_eq!(Colour::Green.meta(), "Green");
     }
 
+    #[test]
+    fn test_str_meta_display(){
+        use std::str::FromStr;
+
+        #[derive(Debug, Eq, PartialEq)]
+        enum Colour
+        {
+            Red,
+            Orange,
+            Green
+        }
+
+        str_meta!{
+            Colour, &'static str;
+            Red, "Red";
+            Orange, "Orange";
+            Green, "Green"
+        }
+
+        assert_eq!(Colour::Orange.to_string(), "Orange");
+        assert_eq!(Colour::from_str("Green"), Ok(Colour::Green));
+        assert_eq!(
+            Colour::from_str("Purple"),
+            Err(MetaParseError { input: "Purple".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_ordinal(){
+        #[derive(Debug, Eq, PartialEq)]
+        enum Colour
+        {
+            Red,
+            Orange,
+            Green
+        }
+
+        meta!{
+            Colour, &'static str;
+            Red, "Red";
+            Orange, "Orange";
+            Green, "Green"
+        }
+
+        assert_eq!(Colour::VARIANT_COUNT, 3);
+        assert_eq!(Colour::Orange.ordinal(), 1);
+        assert_eq!(Colour::from_ordinal(2), Some(Colour::Green));
+        assert_eq!(Colour::from_ordinal(3), None);
+    }
+
+    #[test]
+    fn test_lazy_ordinal(){
+        #[derive(Debug, Eq, PartialEq)]
+        enum Colour
+        {
+            Red,
+            Orange,
+            Green
+        }
+
+        lazy_meta!{
+            Colour, String, TEST3;
+            Red, "Red".to_string();
+            Orange, "Orange".to_string();
+            Green, "Green".to_string();
+        }
+
+        assert_eq!(Colour::VARIANT_COUNT, 3);
+        assert_eq!(Colour::Orange.ordinal(), 1);
+        assert_eq!(Colour::from_ordinal(0), Some(Colour::Red));
+        assert_eq!(Colour::from_ordinal(3), None);
+    }
+
+    #[test]
+    fn test_meta_props(){
+        enum Colour
+        {
+            Red,
+            Orange,
+            Green
+        }
+
+        meta_props!{
+            Colour, i64;
+            Red, { "r" => 255, "g" => 0, "b" => 0 };
+            Orange, { "r" => 255, "g" => 165, "b" => 0 };
+            Green, { "r" => 0, "g" => 255, "b" => 0 }
+        }
+
+        assert_eq!(Colour::Orange.get_prop("g"), Some(&165));
+        assert_eq!(Colour::Orange.get_prop("missing"), None);
+        assert_eq!(Colour::Red.prop_keys(), &["r", "g", "b"]);
+        assert_eq!(Colour::Green.get_prop("r"), Some(&0));
+    }
+
     #[test]
     fn test_lazy_meta(){
         enum Colour
@@ -336,6 +693,50 @@ mod test {
         assert_eq!(Colour::Green.meta(), "Green");
     }
 
+    #[test]
+    fn test_from_meta(){
+        #[derive(Debug, Eq, PartialEq)]
+        enum Colour
+        {
+            Red,
+            Orange,
+            Green
+        }
+
+        meta!{
+            Colour, &'static str;
+            Red, "Red";
+            Orange, "Orange";
+            Green, "Green"
+        }
+
+        assert_eq!(Colour::from_meta(&"Orange"), Some(Colour::Orange));
+        assert_eq!(Colour::from_meta(&"Purple"), None);
+    }
+
+    #[test]
+    fn test_lazy_from_meta(){
+        #[derive(Debug, Eq, PartialEq)]
+        enum Colour
+        {
+            Red,
+            Orange,
+            Green
+        }
+
+        lazy_meta!{
+            Colour, String, TEST2;
+            Red, "Red".to_string();
+            Orange, "Orange".to_string();
+            Green, "Green".to_string();
+        }
+
+        let orange = "Orange".to_string();
+        let purple = "Purple".to_string();
+        assert_eq!(Colour::from_meta(&&orange), Some(Colour::Orange));
+        assert_eq!(Colour::from_meta(&&purple), None);
+    }
+
     #[test]
     fn test_lazy_all(){
         #[derive(Debug, Eq, PartialEq)]
@@ -359,4 +760,23 @@ mod test {
                         Colour::Green]
                    );
     }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_meta(){
+        #[derive(Meta, Debug, Eq, PartialEq)]
+        #[meta(ty = "&'static str")]
+        enum Colour
+        {
+            #[meta("Red")]
+            Red,
+            #[meta("Orange")]
+            Orange,
+            #[meta("Green")]
+            Green,
+        }
+
+        assert_eq!(Colour::Orange.meta(), "Orange");
+        assert_eq!(Colour::from_meta(&"Green"), Some(Colour::Green));
+    }
 }